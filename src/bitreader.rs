@@ -0,0 +1,172 @@
+/**
+ * Copyright (c) Ben Serrano. All rights reserved.
+ * Licensed under the MIT License. See LICENSE in the project root for license information
+ */
+use std::io::{self, Error, Read};
+
+/// A shared MSB-first bit-level reader over any byte source. STREAMINFO's
+/// packed bitfields, frame headers, and Rice-coded residuals all build on
+/// this instead of each hand-rolling their own byte/bit bookkeeping.
+pub struct BitReader<R: Read> {
+    source: R,
+    current_byte: u8,
+    bits_remaining: u32,
+}
+
+impl<R: Read> BitReader<R> {
+    pub fn new(source: R) -> Self {
+        BitReader {
+            source,
+            current_byte: 0,
+            bits_remaining: 0,
+        }
+    }
+
+    /// Borrows the underlying byte source, e.g. to inspect state a wrapper
+    /// reader accumulates as bytes are pulled through it.
+    pub fn get_ref(&self) -> &R {
+        &self.source
+    }
+
+    fn fetch_byte(&mut self) -> Result<u8, Error> {
+        let mut raw_byte = [0u8; 1];
+        let bytes_read = self.source.read(&mut raw_byte)?;
+        if bytes_read != 1 {
+            return Err(Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Unexpected end of bit stream",
+            ));
+        }
+        Ok(raw_byte[0])
+    }
+
+    pub fn read_bit(&mut self) -> Result<u32, Error> {
+        if self.bits_remaining == 0 {
+            self.current_byte = self.fetch_byte()?;
+            self.bits_remaining = 8;
+        }
+
+        self.bits_remaining -= 1;
+        Ok(u32::from((self.current_byte >> self.bits_remaining) & 1))
+    }
+
+    pub fn read_bits(&mut self, num_bits: u32) -> Result<u64, Error> {
+        let mut value = 0u64;
+        for _ in 0..num_bits {
+            value = (value << 1) | u64::from(self.read_bit()?);
+        }
+        Ok(value)
+    }
+
+    /// Reads `num_bits` as a twos-complement signed integer.
+    pub fn read_signed(&mut self, num_bits: u32) -> Result<i64, Error> {
+        if num_bits == 0 {
+            return Ok(0);
+        }
+        let raw = self.read_bits(num_bits)?;
+        let shift = 64 - num_bits;
+        Ok(((raw << shift) as i64) >> shift)
+    }
+
+    /// Counts leading zero bits up to (and consuming) the terminating one bit.
+    pub fn read_unary(&mut self) -> Result<u32, Error> {
+        let mut count = 0u32;
+        while self.read_bit()? == 0 {
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Reads a FLAC UTF-8-style coded number, as used for the frame header's
+    /// frame-or-sample number.
+    pub fn read_utf8_number(&mut self) -> Result<u64, Error> {
+        let lead_byte = self.read_bits(8)? as u8;
+        if lead_byte & 0b1000_0000 == 0 {
+            return Ok(u64::from(lead_byte));
+        }
+
+        let mut leading_ones = 0u32;
+        let mut probe = 0b1000_0000u8;
+        while lead_byte & probe != 0 {
+            leading_ones += 1;
+            probe >>= 1;
+        }
+
+        if !(2..=7).contains(&leading_ones) {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid UTF-8 coded number lead byte",
+            ));
+        }
+
+        let lead_mask = if leading_ones == 7 {
+            0
+        } else {
+            0xFFu8 >> (leading_ones + 1)
+        };
+        let mut value = u64::from(lead_byte & lead_mask);
+        for _ in 1..leading_ones {
+            let continuation_byte = self.read_bits(8)? as u8;
+            if continuation_byte & 0b1100_0000 != 0b1000_0000 {
+                return Err(Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Invalid UTF-8 coded number continuation byte",
+                ));
+            }
+            value = (value << 6) | u64::from(continuation_byte & 0b0011_1111);
+        }
+
+        Ok(value)
+    }
+
+    /// Discards any partially-read bits so the next read starts at a byte boundary.
+    pub fn byte_align(&mut self) {
+        self.bits_remaining = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn read_bits_crosses_byte_boundary() {
+        let mut reader = BitReader::new(Cursor::new([0b1010_1010u8, 0b1111_0000u8]));
+        assert_eq!(reader.read_bits(4).unwrap(), 0b1010);
+        assert_eq!(reader.read_bits(8).unwrap(), 0b1010_1111);
+        assert_eq!(reader.read_bits(4).unwrap(), 0b0000);
+    }
+
+    #[test]
+    fn read_signed_sign_extends() {
+        let mut reader = BitReader::new(Cursor::new([0b1110_0000u8]));
+        assert_eq!(reader.read_signed(4).unwrap(), -2);
+    }
+
+    #[test]
+    fn read_signed_zero_bits_is_zero() {
+        let mut reader = BitReader::new(Cursor::new([0xFFu8]));
+        assert_eq!(reader.read_signed(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_unary_counts_leading_zeros() {
+        let mut reader = BitReader::new(Cursor::new([0b0001_0000u8]));
+        assert_eq!(reader.read_unary().unwrap(), 3);
+    }
+
+    #[test]
+    fn read_utf8_number_single_byte() {
+        let mut reader = BitReader::new(Cursor::new([0x42u8]));
+        assert_eq!(reader.read_utf8_number().unwrap(), 0x42);
+    }
+
+    #[test]
+    fn read_utf8_number_seven_byte_lead_does_not_overflow() {
+        let mut reader = BitReader::new(Cursor::new([
+            0xFEu8, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+        ]));
+        assert_eq!(reader.read_utf8_number().unwrap(), 0);
+    }
+}
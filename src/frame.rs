@@ -0,0 +1,703 @@
+/**
+ * Copyright (c) Ben Serrano. All rights reserved.
+ * Licensed under the MIT License. See LICENSE in the project root for license information
+ */
+use std::io::{self, Error, Read};
+
+use crate::bitreader::BitReader;
+use crate::md5::Md5;
+use crate::metadata::Streaminfo;
+
+const FRAME_SYNC_CODE: u16 = 0b11_1111_1111_1110;
+
+const FIXED_COEFFICIENTS: [&[i64]; 5] = [&[], &[1], &[2, -1], &[3, -3, 1], &[4, -6, 4, -1]];
+
+enum BlockingStrategy {
+    Fixed,
+    Variable,
+}
+
+enum ChannelAssignment {
+    Independent(u8),
+    LeftSide,
+    RightSide,
+    MidSide,
+}
+
+impl ChannelAssignment {
+    fn channel_count(&self) -> u8 {
+        match self {
+            ChannelAssignment::Independent(num_channels) => *num_channels,
+            ChannelAssignment::LeftSide | ChannelAssignment::RightSide | ChannelAssignment::MidSide => 2,
+        }
+    }
+}
+
+struct FrameHeader {
+    blocking_strategy: BlockingStrategy,
+    block_size: u32,
+    sample_rate: u32,
+    channel_assignment: ChannelAssignment,
+    sample_size: u32,
+    frame_or_sample_number: u64,
+}
+
+impl FrameHeader {
+    /// The sample number of this frame's first sample. Fixed-blocksize streams
+    /// code the frame number, so it's scaled by the stream's max block size;
+    /// variable-blocksize streams code the sample number directly.
+    fn starting_sample(&self, streaminfo: &Streaminfo) -> u64 {
+        match self.blocking_strategy {
+            BlockingStrategy::Fixed => self.frame_or_sample_number * u64::from(streaminfo.max_blk_size),
+            BlockingStrategy::Variable => self.frame_or_sample_number,
+        }
+    }
+}
+
+/// A decoded FLAC audio frame: one block of PCM, held as one sample buffer per channel.
+pub struct Frame {
+    pub sample_rate: u32,
+    pub bits_per_sample: u32,
+    pub block_size: u32,
+    /// The sample number of this frame's first sample, for seeking/resyncing.
+    pub starting_sample: u64,
+    pub channel_samples: Vec<Vec<i32>>,
+}
+
+fn update_crc8(crc: u8, byte: u8) -> u8 {
+    let mut crc = crc ^ byte;
+    for _ in 0..8 {
+        crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+    }
+    crc
+}
+
+fn update_crc16(crc: u16, byte: u8) -> u16 {
+    let mut crc = crc ^ (u16::from(byte) << 8);
+    for _ in 0..8 {
+        crc = if crc & 0x8000 != 0 {
+            (crc << 1) ^ 0x8005
+        } else {
+            crc << 1
+        };
+    }
+    crc
+}
+
+/// A `Read` adapter that tracks the running CRC-8 and CRC-16 checksums FLAC
+/// frames are footed with, as bytes pass through it. Wrapped in a shared
+/// [`BitReader`], this gives the frame decoder both bit-level access and the
+/// checksum bookkeeping frame headers and footers need.
+struct ChecksummedReader<'a, R: Read> {
+    inner: &'a mut R,
+    crc8: u8,
+    crc16: u16,
+}
+
+impl<'a, R: Read> ChecksummedReader<'a, R> {
+    fn new(inner: &'a mut R) -> Self {
+        ChecksummedReader {
+            inner,
+            crc8: 0,
+            crc16: 0,
+        }
+    }
+}
+
+impl<'a, R: Read> Read for ChecksummedReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        for &byte in &buf[..bytes_read] {
+            self.crc8 = update_crc8(self.crc8, byte);
+            self.crc16 = update_crc16(self.crc16, byte);
+        }
+        Ok(bytes_read)
+    }
+}
+
+fn parse_frame_header(
+    reader: &mut BitReader<ChecksummedReader<'_, impl Read>>,
+    streaminfo: &Streaminfo,
+) -> Result<FrameHeader, Error> {
+    let sync_and_flags = reader.read_bits(16)?;
+    let sync_code = (sync_and_flags >> 2) as u16;
+    if sync_code != FRAME_SYNC_CODE {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid frame sync code: {sync_code:#06b}"),
+        ));
+    }
+    if sync_and_flags & 0b10 != 0 {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Reserved frame header bit is set",
+        ));
+    }
+    let blocking_strategy = if sync_and_flags & 0b1 != 0 {
+        BlockingStrategy::Variable
+    } else {
+        BlockingStrategy::Fixed
+    };
+
+    let block_size_code = reader.read_bits(4)? as u8;
+    let sample_rate_code = reader.read_bits(4)? as u8;
+    let channel_assignment_code = reader.read_bits(4)? as u8;
+    let sample_size_code = reader.read_bits(3)? as u8;
+    reader.read_bits(1)?;
+
+    let frame_or_sample_number = reader.read_utf8_number()?;
+
+    let block_size = match block_size_code {
+        0 => {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                "Reserved block size code",
+            ))
+        }
+        1 => 192,
+        2..=5 => 576 << (block_size_code - 2),
+        6 => reader.read_bits(8)? as u32 + 1,
+        7 => reader.read_bits(16)? as u32 + 1,
+        8..=15 => 256 << (block_size_code - 8),
+        _ => unreachable!("block size code is 4 bits"),
+    };
+
+    let sample_rate = match sample_rate_code {
+        0 => streaminfo.sample_rate,
+        1 => 88_200,
+        2 => 176_400,
+        3 => 192_000,
+        4 => 8_000,
+        5 => 16_000,
+        6 => 22_050,
+        7 => 24_000,
+        8 => 32_000,
+        9 => 44_100,
+        10 => 48_000,
+        11 => 96_000,
+        12 => reader.read_bits(8)? as u32 * 1_000,
+        13 => reader.read_bits(16)? as u32,
+        14 => reader.read_bits(16)? as u32 * 10,
+        15 => {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid sample rate code",
+            ))
+        }
+        _ => unreachable!("sample rate code is 4 bits"),
+    };
+
+    let channel_assignment = match channel_assignment_code {
+        0..=7 => ChannelAssignment::Independent(channel_assignment_code + 1),
+        8 => ChannelAssignment::LeftSide,
+        9 => ChannelAssignment::RightSide,
+        10 => ChannelAssignment::MidSide,
+        e => {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Reserved channel assignment code {e}"),
+            ))
+        }
+    };
+
+    let sample_size = match sample_size_code {
+        0 => streaminfo.bits_per_sample,
+        1 => 8,
+        2 => 12,
+        4 => 16,
+        5 => 20,
+        6 => 24,
+        e => {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Reserved sample size code {e}"),
+            ))
+        }
+    };
+
+    let expected_crc8 = reader.get_ref().crc8;
+    let header_crc8 = reader.read_bits(8)? as u8;
+    if header_crc8 != expected_crc8 {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Frame header CRC-8 mismatch",
+        ));
+    }
+
+    Ok(FrameHeader {
+        blocking_strategy,
+        block_size,
+        sample_rate,
+        channel_assignment,
+        sample_size,
+        frame_or_sample_number,
+    })
+}
+
+fn decode_rice_value(reader: &mut BitReader<ChecksummedReader<'_, impl Read>>, rice_parameter: u32) -> Result<i32, Error> {
+    let quotient = reader.read_unary()?;
+    let remainder = reader.read_bits(rice_parameter)?;
+    let zigzag = (u64::from(quotient) << rice_parameter) | remainder;
+    Ok(if zigzag & 1 == 0 {
+        (zigzag >> 1) as i32
+    } else {
+        -(((zigzag >> 1) + 1) as i32)
+    })
+}
+
+fn decode_residuals(
+    reader: &mut BitReader<ChecksummedReader<'_, impl Read>>,
+    block_size: u32,
+    predictor_order: u32,
+) -> Result<Vec<i32>, Error> {
+    let coding_method = reader.read_bits(2)?;
+    let (rice_parameter_bits, escape_parameter) = match coding_method {
+        0 => (4u32, 0b1111u64),
+        1 => (5u32, 0b1_1111u64),
+        e => {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unknown residual coding method {e}"),
+            ))
+        }
+    };
+
+    let partition_order = reader.read_bits(4)? as u32;
+    let partition_count = 1u32 << partition_order;
+    if partition_count == 0 || !block_size.is_multiple_of(partition_count) {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Partition count does not evenly divide block size",
+        ));
+    }
+
+    let samples_per_partition = block_size / partition_count;
+    if samples_per_partition < predictor_order {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Partition is too small for the predictor order",
+        ));
+    }
+
+    let mut residuals = Vec::with_capacity((block_size - predictor_order) as usize);
+    for partition in 0..partition_count {
+        let partition_len = if partition == 0 {
+            samples_per_partition - predictor_order
+        } else {
+            samples_per_partition
+        };
+
+        let rice_parameter = reader.read_bits(rice_parameter_bits)?;
+        if rice_parameter == escape_parameter {
+            let raw_bits = reader.read_bits(5)? as u32;
+            for _ in 0..partition_len {
+                residuals.push(reader.read_signed(raw_bits)? as i32);
+            }
+        } else {
+            for _ in 0..partition_len {
+                residuals.push(decode_rice_value(reader, rice_parameter as u32)?);
+            }
+        }
+    }
+
+    Ok(residuals)
+}
+
+fn decode_constant_subframe(
+    reader: &mut BitReader<ChecksummedReader<'_, impl Read>>,
+    block_size: u32,
+    bits_per_sample: u32,
+) -> Result<Vec<i32>, Error> {
+    let value = reader.read_signed(bits_per_sample)? as i32;
+    Ok(vec![value; block_size as usize])
+}
+
+fn decode_verbatim_subframe(
+    reader: &mut BitReader<ChecksummedReader<'_, impl Read>>,
+    block_size: u32,
+    bits_per_sample: u32,
+) -> Result<Vec<i32>, Error> {
+    (0..block_size)
+        .map(|_| reader.read_signed(bits_per_sample).map(|value| value as i32))
+        .collect()
+}
+
+fn decode_fixed_subframe(
+    reader: &mut BitReader<ChecksummedReader<'_, impl Read>>,
+    block_size: u32,
+    bits_per_sample: u32,
+    order: u32,
+) -> Result<Vec<i32>, Error> {
+    let mut samples = Vec::with_capacity(block_size as usize);
+    for _ in 0..order {
+        samples.push(reader.read_signed(bits_per_sample)? as i32);
+    }
+
+    let residuals = decode_residuals(reader, block_size, order)?;
+    let coefficients = FIXED_COEFFICIENTS[order as usize];
+    for residual in residuals {
+        let history_start = samples.len();
+        let prediction: i64 = coefficients
+            .iter()
+            .enumerate()
+            .map(|(j, coefficient)| coefficient * i64::from(samples[history_start - 1 - j]))
+            .sum();
+        samples.push((prediction + i64::from(residual)) as i32);
+    }
+
+    Ok(samples)
+}
+
+fn decode_lpc_subframe(
+    reader: &mut BitReader<ChecksummedReader<'_, impl Read>>,
+    block_size: u32,
+    bits_per_sample: u32,
+    order: u32,
+) -> Result<Vec<i32>, Error> {
+    let mut samples = Vec::with_capacity(block_size as usize);
+    for _ in 0..order {
+        samples.push(reader.read_signed(bits_per_sample)? as i32);
+    }
+
+    let precision = reader.read_bits(4)? as u32 + 1;
+    let shift = reader.read_bits(5)? as u32;
+    let mut coefficients = Vec::with_capacity(order as usize);
+    for _ in 0..order {
+        coefficients.push(reader.read_signed(precision)?);
+    }
+
+    let residuals = decode_residuals(reader, block_size, order)?;
+    for residual in residuals {
+        let history_start = samples.len();
+        let prediction: i64 = coefficients
+            .iter()
+            .enumerate()
+            .map(|(j, coefficient)| coefficient * i64::from(samples[history_start - 1 - j]))
+            .sum();
+        samples.push(((prediction >> shift) + i64::from(residual)) as i32);
+    }
+
+    Ok(samples)
+}
+
+fn decode_subframe(
+    reader: &mut BitReader<ChecksummedReader<'_, impl Read>>,
+    block_size: u32,
+    bits_per_sample: u32,
+) -> Result<Vec<i32>, Error> {
+    reader.read_bits(1)?;
+    let subframe_type = reader.read_bits(6)? as u8;
+    let has_wasted_bits = reader.read_bits(1)? != 0;
+    let wasted_bits = if has_wasted_bits {
+        reader.read_unary()? + 1
+    } else {
+        0
+    };
+    let effective_bits = bits_per_sample - wasted_bits;
+
+    let mut samples = match subframe_type {
+        0b000000 => decode_constant_subframe(reader, block_size, effective_bits)?,
+        0b000001 => decode_verbatim_subframe(reader, block_size, effective_bits)?,
+        0b001000..=0b001100 => {
+            let order = u32::from(subframe_type & 0b0000_0111);
+            decode_fixed_subframe(reader, block_size, effective_bits, order)?
+        }
+        0b100000..=0b111111 => {
+            let order = u32::from(subframe_type & 0b0001_1111) + 1;
+            decode_lpc_subframe(reader, block_size, effective_bits, order)?
+        }
+        e => {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Reserved subframe type {e:#08b}"),
+            ))
+        }
+    };
+
+    if wasted_bits > 0 {
+        for sample in samples.iter_mut() {
+            *sample <<= wasted_bits;
+        }
+    }
+
+    Ok(samples)
+}
+
+fn apply_channel_decorrelation(
+    channel_assignment: &ChannelAssignment,
+    subframes: Vec<Vec<i32>>,
+) -> Vec<Vec<i32>> {
+    match channel_assignment {
+        ChannelAssignment::Independent(_) => subframes,
+        ChannelAssignment::LeftSide => {
+            let left = &subframes[0];
+            let side = &subframes[1];
+            let right = left.iter().zip(side.iter()).map(|(l, s)| l - s).collect();
+            vec![left.clone(), right]
+        }
+        ChannelAssignment::RightSide => {
+            let side = &subframes[0];
+            let right = &subframes[1];
+            let left = right.iter().zip(side.iter()).map(|(r, s)| r + s).collect();
+            vec![left, right.clone()]
+        }
+        ChannelAssignment::MidSide => {
+            let mid = &subframes[0];
+            let side = &subframes[1];
+            let mut left = Vec::with_capacity(mid.len());
+            let mut right = Vec::with_capacity(mid.len());
+            for (&m, &s) in mid.iter().zip(side.iter()) {
+                let mid_shifted = (m << 1) | (s & 1);
+                left.push((mid_shifted + s) >> 1);
+                right.push((mid_shifted - s) >> 1);
+            }
+            vec![left, right]
+        }
+    }
+}
+
+/// Decodes a single FLAC audio frame starting at the current position of `data`,
+/// using `streaminfo` to resolve any header fields that fall back to stream defaults.
+pub fn decode_frame(streaminfo: &Streaminfo, data: &mut impl Read) -> Result<Frame, Error> {
+    let mut reader = BitReader::new(ChecksummedReader::new(data));
+    let header = parse_frame_header(&mut reader, streaminfo)?;
+
+    let channel_count = header.channel_assignment.channel_count();
+    let mut subframes = Vec::with_capacity(channel_count as usize);
+    for channel in 0..channel_count {
+        let bits_per_sample = match (&header.channel_assignment, channel) {
+            (ChannelAssignment::LeftSide, 1)
+            | (ChannelAssignment::RightSide, 0)
+            | (ChannelAssignment::MidSide, 1) => header.sample_size + 1,
+            _ => header.sample_size,
+        };
+        subframes.push(decode_subframe(&mut reader, header.block_size, bits_per_sample)?);
+    }
+
+    let channel_samples = apply_channel_decorrelation(&header.channel_assignment, subframes);
+
+    reader.byte_align();
+    let expected_crc16 = reader.get_ref().crc16;
+    let footer_crc16 = reader.read_bits(16)? as u16;
+    if footer_crc16 != expected_crc16 {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Frame footer CRC-16 mismatch",
+        ));
+    }
+
+    let starting_sample = header.starting_sample(streaminfo);
+
+    Ok(Frame {
+        sample_rate: header.sample_rate,
+        bits_per_sample: header.sample_size,
+        block_size: header.block_size,
+        starting_sample,
+        channel_samples,
+    })
+}
+
+/// Feeds a decoded frame's PCM into `hasher` in the exact order libFLAC hashes
+/// it: per sample, per channel, little-endian, packed to `bits_per_sample`.
+fn feed_frame_to_hasher(hasher: &mut Md5, frame: &Frame) {
+    let byte_width = frame.bits_per_sample.div_ceil(8) as usize;
+    for sample_index in 0..frame.block_size as usize {
+        for channel in &frame.channel_samples {
+            let sample_bytes = channel[sample_index].to_le_bytes();
+            hasher.update(&sample_bytes[..byte_width]);
+        }
+    }
+}
+
+/// Scans `data` byte-by-byte for the next `0xFF 0xF8`-style frame sync
+/// (`0xFF` followed by a byte whose top 7 bits are `1111_100`), returning the
+/// two sync bytes it consumed so the caller can resume parsing right at the
+/// frame header. This lets the frame-consuming loop recover its footing when
+/// handed a buffer that does not begin exactly on a frame boundary.
+fn resync_to_frame_header(data: &mut impl Read) -> Result<[u8; 2], Error> {
+    let mut window = [0u8; 2];
+    let mut filled = 0usize;
+
+    loop {
+        let mut next_byte = [0u8; 1];
+        let bytes_read = data.read(&mut next_byte)?;
+        if bytes_read != 1 {
+            return Err(Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "No frame sync found before end of stream",
+            ));
+        }
+
+        if filled < 2 {
+            window[filled] = next_byte[0];
+            filled += 1;
+        } else {
+            window[0] = window[1];
+            window[1] = next_byte[0];
+        }
+
+        if filled == 2 && window[0] == 0xFF && window[1] & 0b1111_1110 == 0b1111_1000 {
+            return Ok(window);
+        }
+    }
+}
+
+/// A `Read` adapter that replays two already-consumed sync bytes before
+/// falling through to the underlying stream, so a frame decoder that expects
+/// to read the sync code itself can be handed a reader that resumed mid-stream.
+struct PrefixedReader<'a, R: Read> {
+    prefix: [u8; 2],
+    prefix_pos: usize,
+    inner: &'a mut R,
+}
+
+impl<'a, R: Read> Read for PrefixedReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.prefix_pos < self.prefix.len() {
+            let remaining = &self.prefix[self.prefix_pos..];
+            let bytes_copied = remaining.len().min(buf.len());
+            buf[..bytes_copied].copy_from_slice(&remaining[..bytes_copied]);
+            self.prefix_pos += bytes_copied;
+            return Ok(bytes_copied);
+        }
+        self.inner.read(buf)
+    }
+}
+
+/// Decodes every frame in `data` until `streaminfo.total_sample_count` samples
+/// have been produced (or, for a stream of unknown length, until EOF).
+///
+/// `streaminfo` may come from a full FLAC file's STREAMINFO block or be
+/// supplied directly by a demuxer that only carries the raw 34-byte payload;
+/// either way `data` only needs to begin at (or before) a frame sync, since
+/// each frame is located by scanning for its sync code rather than assuming
+/// the reader's position is already aligned.
+///
+/// When `verify` is set, the decoded PCM is hashed as it is produced and
+/// compared against `streaminfo.md5_checksum`; a zero checksum is treated as
+/// "not present" per spec and skips verification.
+pub fn decode_frames_with_streaminfo(
+    streaminfo: &Streaminfo,
+    data: &mut impl Read,
+    verify: bool,
+) -> Result<Vec<Vec<i32>>, Error> {
+    let mut channel_samples: Vec<Vec<i32>> = Vec::new();
+    let mut hasher = Md5::new();
+    let mut decoded_samples = 0u64;
+
+    loop {
+        if streaminfo.total_sample_count != 0 && decoded_samples >= streaminfo.total_sample_count {
+            break;
+        }
+
+        let sync_prefix = match resync_to_frame_header(data) {
+            Ok(prefix) => prefix,
+            Err(e)
+                if e.kind() == io::ErrorKind::UnexpectedEof
+                    && streaminfo.total_sample_count == 0 =>
+            {
+                break
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut prefixed_data = PrefixedReader {
+            prefix: sync_prefix,
+            prefix_pos: 0,
+            inner: &mut *data,
+        };
+        let frame = match decode_frame(streaminfo, &mut prefixed_data) {
+            Ok(frame) => frame,
+            Err(e)
+                if e.kind() == io::ErrorKind::UnexpectedEof
+                    && streaminfo.total_sample_count == 0 =>
+            {
+                break
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verify {
+            feed_frame_to_hasher(&mut hasher, &frame);
+        }
+
+        decoded_samples += u64::from(frame.block_size);
+        for (channel, samples) in frame.channel_samples.into_iter().enumerate() {
+            if channel_samples.len() <= channel {
+                channel_samples.push(Vec::new());
+            }
+            channel_samples[channel].extend(samples);
+        }
+    }
+
+    if verify && streaminfo.md5_checksum != 0 {
+        let computed_checksum = hasher.finalize();
+        if computed_checksum != streaminfo.md5_checksum {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                "Decoded audio does not match STREAMINFO MD5 checksum",
+            ));
+        }
+    }
+
+    Ok(channel_samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn decode_rice_value_zero() {
+        let mut cursor = Cursor::new([0b1000_0000u8]);
+        let mut reader = BitReader::new(ChecksummedReader::new(&mut cursor));
+        assert_eq!(decode_rice_value(&mut reader, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn decode_rice_value_negative_one() {
+        let mut cursor = Cursor::new([0b0100_0000u8]);
+        let mut reader = BitReader::new(ChecksummedReader::new(&mut cursor));
+        assert_eq!(decode_rice_value(&mut reader, 0).unwrap(), -1);
+    }
+
+    #[test]
+    fn decode_rice_value_with_remainder_bits() {
+        // k=2, zigzag=6 (quotient=1, remainder=2) decodes to value 3.
+        let mut cursor = Cursor::new([0b0110_0000u8]);
+        let mut reader = BitReader::new(ChecksummedReader::new(&mut cursor));
+        assert_eq!(decode_rice_value(&mut reader, 2).unwrap(), 3);
+    }
+
+    #[test]
+    fn channel_decorrelation_left_side() {
+        let subframes = vec![vec![10, 20], vec![2, 4]];
+        let result = apply_channel_decorrelation(&ChannelAssignment::LeftSide, subframes);
+        assert_eq!(result, vec![vec![10, 20], vec![8, 16]]);
+    }
+
+    #[test]
+    fn channel_decorrelation_right_side() {
+        let subframes = vec![vec![2, 4], vec![8, 16]];
+        let result = apply_channel_decorrelation(&ChannelAssignment::RightSide, subframes);
+        assert_eq!(result, vec![vec![10, 20], vec![8, 16]]);
+    }
+
+    #[test]
+    fn channel_decorrelation_mid_side() {
+        let subframes = vec![vec![15], vec![2]];
+        let result = apply_channel_decorrelation(&ChannelAssignment::MidSide, subframes);
+        assert_eq!(result, vec![vec![16], vec![14]]);
+    }
+
+    #[test]
+    fn decode_residuals_rejects_partition_smaller_than_predictor_order() {
+        // partition_order=0 (1 partition) with a 4-sample block and predictor order 8
+        // makes the first partition underflow if not validated.
+        let mut cursor = Cursor::new(vec![0u8; 4]);
+        let mut reader = BitReader::new(ChecksummedReader::new(&mut cursor));
+        let result = decode_residuals(&mut reader, 4, 8);
+        assert!(result.is_err());
+    }
+}
@@ -2,6 +2,9 @@
  * Copyright (c) Ben Serrano. All rights reserved.
  * Licensed under the MIT License. See LICENSE in the project root for license information
  */
+pub mod bitreader;
+pub mod frame;
+pub mod md5;
 pub mod metadata;
 pub mod showable;
 
@@ -23,6 +26,36 @@ impl showable::Showable for metadata::Header {
     }
 }
 
+impl showable::Showable for metadata::VorbisComment {
+    fn show_details(&self) {
+        println!("VorbisComment:");
+        println!("vendor_string: {0}", self.vendor_string);
+        for (field, value) in &self.comments {
+            println!("{field}: {value}");
+        }
+        println!();
+    }
+}
+
+impl showable::Showable for metadata::SeekTable {
+    fn show_details(&self) {
+        println!("SeekTable:");
+        println!("num_seek_points: {0}", self.seek_points.len());
+        println!();
+    }
+}
+
+impl showable::Showable for metadata::Picture {
+    fn show_details(&self) {
+        println!("Picture:");
+        println!("picture_type: {0}", self.picture_type);
+        println!("mime_type: {0}", self.mime_type);
+        println!("width: {0}", self.width);
+        println!("height: {0}", self.height);
+        println!();
+    }
+}
+
 impl showable::Showable for metadata::Streaminfo {
     fn show_details(&self) {
         println!("Streaminfo:");
@@ -43,7 +76,22 @@ fn is_valid_flac_hdr(flac_hdr: &[u8; 4]) -> bool {
     flac_hdr == FLAC_HEADER
 }
 
-fn read_flac_hdr(flac_file: &mut BufReader<fs::File>) -> Result<(), Error> {
+/// The subset of metadata blocks callers commonly need, gathered as
+/// `read_flac_hdr` walks the metadata block chain.
+#[derive(Default)]
+pub struct ParsedMetadata {
+    pub vorbis_comment: Option<metadata::VorbisComment>,
+    pub seek_table: Option<metadata::SeekTable>,
+    pub pictures: Vec<metadata::Picture>,
+}
+
+/// Walks the metadata block chain of an already-opened FLAC file, from the
+/// leading `fLaC` marker through the final block. This is the metadata half
+/// of decoding a whole file; pair it with [`frame::decode_frames_with_streaminfo`]
+/// to consume the audio frames that follow.
+pub fn read_flac_hdr(
+    flac_file: &mut BufReader<fs::File>,
+) -> Result<(metadata::Streaminfo, ParsedMetadata), Error> {
     let mut flac_hdr = [0u8; 4];
     match flac_file.read(&mut flac_hdr) {
         Ok(bytes_read) => {
@@ -68,7 +116,8 @@ fn read_flac_hdr(flac_file: &mut BufReader<fs::File>) -> Result<(), Error> {
     }
 
     let mut is_final_block = false;
-    let mut stream_info: metadata::Streaminfo;
+    let mut stream_info: Option<metadata::Streaminfo> = None;
+    let mut parsed_metadata = ParsedMetadata::default();
     while !is_final_block {
         let mut raw_metadata_blk_hdr = [0u8; 4];
         match flac_file.read(&mut raw_metadata_blk_hdr) {
@@ -97,16 +146,48 @@ fn read_flac_hdr(flac_file: &mut BufReader<fs::File>) -> Result<(), Error> {
         let _ = flac_file.read(&mut raw_metadata);
 
         if metadata_blk_hdr.blk_type == metadata::Type::Streaminfo {
-            stream_info = match metadata::Streaminfo::new(raw_metadata.as_slice()) {
+            let parsed_stream_info = match metadata::Streaminfo::new(raw_metadata.as_slice()) {
                 Ok(si) => si,
                 Err(e) => return Err(e),
             };
-            stream_info.show_details();
+            parsed_stream_info.show_details();
+            stream_info = Some(parsed_stream_info);
+        } else if metadata_blk_hdr.blk_type == metadata::Type::VorbisComment {
+            let parsed_comment = match metadata::VorbisComment::new(raw_metadata.as_slice()) {
+                Ok(vc) => vc,
+                Err(e) => return Err(e),
+            };
+            parsed_comment.show_details();
+            parsed_metadata.vorbis_comment = Some(parsed_comment);
+        } else if metadata_blk_hdr.blk_type == metadata::Type::Seektable {
+            let parsed_seek_table = match metadata::SeekTable::new(raw_metadata.as_slice()) {
+                Ok(st) => st,
+                Err(e) => return Err(e),
+            };
+            parsed_seek_table.show_details();
+            parsed_metadata.seek_table = Some(parsed_seek_table);
+        } else if metadata_blk_hdr.blk_type == metadata::Type::Picture {
+            let parsed_picture = match metadata::Picture::new(raw_metadata.as_slice()) {
+                Ok(p) => p,
+                Err(e) => return Err(e),
+            };
+            parsed_picture.show_details();
+            parsed_metadata.pictures.push(parsed_picture);
         }
         is_final_block = metadata_blk_hdr.is_final_block;
     }
 
-    Ok(())
+    let stream_info = match stream_info {
+        Some(si) => si,
+        None => {
+            return Err(Error::new(
+                io::ErrorKind::InvalidData,
+                "Missing required STREAMINFO block",
+            ))
+        }
+    };
+
+    Ok((stream_info, parsed_metadata))
 }
 
 fn main() -> Result<(), Error> {
@@ -117,10 +198,21 @@ fn main() -> Result<(), Error> {
     };
 
     let mut flac_file = BufReader::new(flac_file);
-    let hdr_result = read_flac_hdr(&mut flac_file);
+    let (stream_info, _parsed_metadata) = match read_flac_hdr(&mut flac_file) {
+        Ok(parsed) => parsed,
+        Err(e) => return Err(e),
+    };
+
+    let channel_samples =
+        match frame::decode_frames_with_streaminfo(&stream_info, &mut flac_file, true) {
+            Ok(channel_samples) => channel_samples,
+            Err(e) => return Err(e),
+        };
+    println!(
+        "Decoded {0} channel(s), {1} samples per channel",
+        channel_samples.len(),
+        channel_samples.first().map_or(0, |channel| channel.len())
+    );
 
-    if hdr_result.is_err() {
-        return hdr_result;
-    }
     return Ok(());
 }
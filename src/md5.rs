@@ -0,0 +1,152 @@
+/**
+ * Copyright (c) Ben Serrano. All rights reserved.
+ * Licensed under the MIT License. See LICENSE in the project root for license information
+ */
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613,
+    0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193,
+    0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d,
+    0x02441453, 0xd8a1e681, 0xe7d3fbc8, 0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed,
+    0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a, 0xfffa3942, 0x8771f681, 0x6d9d6122,
+    0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, 0x289b7ec6, 0xeaa127fa,
+    0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665, 0xf4292244,
+    0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb,
+    0xeb86d391,
+];
+
+/// A minimal RFC 1321 MD5 implementation, used to verify decoded PCM against
+/// the checksum `Streaminfo::md5_checksum` records.
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: Vec<u8>,
+    total_len: u64,
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Md5 {
+            state: [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476],
+            buffer: Vec::with_capacity(64),
+            total_len: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.total_len += data.len() as u64;
+        self.buffer.extend_from_slice(data);
+
+        let mut offset = 0;
+        while self.buffer.len() - offset >= 64 {
+            let mut block = [0u8; 64];
+            block.copy_from_slice(&self.buffer[offset..offset + 64]);
+            self.process_block(&block);
+            offset += 64;
+        }
+        self.buffer.drain(..offset);
+    }
+
+    pub fn finalize(mut self) -> u128 {
+        let bit_len = self.total_len.wrapping_mul(8);
+        self.buffer.push(0x80);
+        while self.buffer.len() % 64 != 56 {
+            self.buffer.push(0);
+        }
+        self.buffer.extend_from_slice(&bit_len.to_le_bytes());
+
+        let mut offset = 0;
+        while offset < self.buffer.len() {
+            let mut block = [0u8; 64];
+            block.copy_from_slice(&self.buffer[offset..offset + 64]);
+            self.process_block(&block);
+            offset += 64;
+        }
+
+        let mut digest = [0u8; 16];
+        for (i, word) in self.state.iter().enumerate() {
+            digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+        }
+        u128::from_be_bytes(digest)
+    }
+
+    fn process_block(&mut self, block: &[u8; 64]) {
+        let mut words = [0u32; 16];
+        for (i, word) in words.iter_mut().enumerate() {
+            *word = u32::from_le_bytes([
+                block[i * 4],
+                block[i * 4 + 1],
+                block[i * 4 + 2],
+                block[i * 4 + 3],
+            ]);
+        }
+
+        let (mut a, mut b, mut c, mut d) =
+            (self.state[0], self.state[1], self.state[2], self.state[3]);
+
+        for i in 0..64 {
+            let (f, source_index) = match i {
+                0..=15 => ((b & c) | (!b & d), i),
+                16..=31 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                32..=47 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+
+            let f = f
+                .wrapping_add(a)
+                .wrapping_add(ROUND_CONSTANTS[i])
+                .wrapping_add(words[source_index]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(SHIFTS[i]));
+        }
+
+        self.state[0] = self.state[0].wrapping_add(a);
+        self.state[1] = self.state[1].wrapping_add(b);
+        self.state[2] = self.state[2].wrapping_add(c);
+        self.state[3] = self.state[3].wrapping_add(d);
+    }
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input() {
+        let mut hasher = Md5::new();
+        hasher.update(b"");
+        assert_eq!(hasher.finalize(), 0xd41d8cd9_8f00b204_e9800998_ecf8427e);
+    }
+
+    #[test]
+    fn known_vector_abc() {
+        let mut hasher = Md5::new();
+        hasher.update(b"abc");
+        assert_eq!(hasher.finalize(), 0x90015098_3cd24fb0_d6963f7d_28e17f72);
+    }
+
+    #[test]
+    fn chunked_updates_match_single_update() {
+        let mut chunked = Md5::new();
+        chunked.update(b"hello, ");
+        chunked.update(b"world");
+
+        let mut whole = Md5::new();
+        whole.update(b"hello, world");
+
+        assert_eq!(chunked.finalize(), whole.finalize());
+    }
+}
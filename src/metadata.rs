@@ -1,5 +1,7 @@
 use core::fmt;
-use std::io::{self, Error};
+use std::io::{self, Cursor, Error};
+
+use crate::bitreader::BitReader;
 
 #[derive(PartialEq)]
 #[repr(u8)]
@@ -97,67 +99,343 @@ impl Header {
     }
 }
 
-impl Streaminfo {
-    pub fn new(raw_streaminfo: &[u8]) -> Result<Self, Error> {
-        eprintln!("Raw data len: {0}", raw_streaminfo.len());
-        if raw_streaminfo.len() < STREAMINFO_SIZE {
+/// IETF Cellar Flac-14 Section 8.7
+#[derive(PartialEq)]
+#[repr(u32)]
+pub enum PictureType {
+    Other = 0,
+    FileIcon32x32,
+    OtherFileIcon,
+    FrontCover,
+    BackCover,
+    LinerNotes,
+    Media,
+    LeadArtist,
+    Artist,
+    Conductor,
+    Band,
+    Composer,
+    Lyricist,
+    RecordingLocation,
+    DuringRecording,
+    DuringPerformance,
+    VideoScreenCapture,
+    BrightColoredFish,
+    Illustration,
+    ArtistLogotype,
+    PublisherLogotype,
+}
+
+impl fmt::Display for PictureType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PictureType::Other => "Other",
+            PictureType::FileIcon32x32 => "32x32 File Icon",
+            PictureType::OtherFileIcon => "Other File Icon",
+            PictureType::FrontCover => "Front Cover",
+            PictureType::BackCover => "Back Cover",
+            PictureType::LinerNotes => "Liner Notes",
+            PictureType::Media => "Media",
+            PictureType::LeadArtist => "Lead Artist",
+            PictureType::Artist => "Artist",
+            PictureType::Conductor => "Conductor",
+            PictureType::Band => "Band",
+            PictureType::Composer => "Composer",
+            PictureType::Lyricist => "Lyricist",
+            PictureType::RecordingLocation => "Recording Location",
+            PictureType::DuringRecording => "During Recording",
+            PictureType::DuringPerformance => "During Performance",
+            PictureType::VideoScreenCapture => "Video Screen Capture",
+            PictureType::BrightColoredFish => "Bright Colored Fish",
+            PictureType::Illustration => "Illustration",
+            PictureType::ArtistLogotype => "Artist Logotype",
+            PictureType::PublisherLogotype => "Publisher Logotype",
+        };
+        write!(f, "PictureType: {name}")
+    }
+}
+
+impl PictureType {
+    fn new(raw_picture_type: u32) -> Result<Self, Error> {
+        match raw_picture_type {
+            0 => Ok(PictureType::Other),
+            1 => Ok(PictureType::FileIcon32x32),
+            2 => Ok(PictureType::OtherFileIcon),
+            3 => Ok(PictureType::FrontCover),
+            4 => Ok(PictureType::BackCover),
+            5 => Ok(PictureType::LinerNotes),
+            6 => Ok(PictureType::Media),
+            7 => Ok(PictureType::LeadArtist),
+            8 => Ok(PictureType::Artist),
+            9 => Ok(PictureType::Conductor),
+            10 => Ok(PictureType::Band),
+            11 => Ok(PictureType::Composer),
+            12 => Ok(PictureType::Lyricist),
+            13 => Ok(PictureType::RecordingLocation),
+            14 => Ok(PictureType::DuringRecording),
+            15 => Ok(PictureType::DuringPerformance),
+            16 => Ok(PictureType::VideoScreenCapture),
+            17 => Ok(PictureType::BrightColoredFish),
+            18 => Ok(PictureType::Illustration),
+            19 => Ok(PictureType::ArtistLogotype),
+            20 => Ok(PictureType::PublisherLogotype),
+            e => Err(Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Found unexpected Picture Type {e}"),
+            )),
+        }
+    }
+}
+
+/// IETF Cellar Flac-14 Section 8.7
+pub struct Picture {
+    pub picture_type: PictureType,
+    pub mime_type: String,
+    pub description: String,
+    pub width: u32,
+    pub height: u32,
+    pub color_depth: u32,
+    pub num_indexed_colors: u32,
+    pub data: Vec<u8>,
+}
+
+impl Picture {
+    pub fn new(raw_picture: &[u8]) -> Result<Self, Error> {
+        let mut offset = 0usize;
+
+        let picture_type = PictureType::new(read_be_u32(raw_picture, &mut offset)?)?;
+
+        let mime_length = read_be_u32(raw_picture, &mut offset)? as usize;
+        let mime_type = read_ascii_string(raw_picture, &mut offset, mime_length)?;
+
+        let description_length = read_be_u32(raw_picture, &mut offset)? as usize;
+        let description = read_utf8_string(raw_picture, &mut offset, description_length)?;
+
+        let width = read_be_u32(raw_picture, &mut offset)?;
+        let height = read_be_u32(raw_picture, &mut offset)?;
+        let color_depth = read_be_u32(raw_picture, &mut offset)?;
+        let num_indexed_colors = read_be_u32(raw_picture, &mut offset)?;
+
+        let data_length = read_be_u32(raw_picture, &mut offset)? as usize;
+        let data = read_raw_bytes(raw_picture, &mut offset, data_length)?;
+
+        Ok(Picture {
+            picture_type,
+            mime_type,
+            description,
+            width,
+            height,
+            color_depth,
+            num_indexed_colors,
+            data,
+        })
+    }
+}
+
+fn read_be_u32(raw_data: &[u8], offset: &mut usize) -> Result<u32, Error> {
+    if *offset + 4 > raw_data.len() {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Picture block truncated",
+        ));
+    }
+
+    let mut raw_u32 = [0u8; 4];
+    raw_u32.copy_from_slice(&raw_data[*offset..*offset + 4]);
+    *offset += 4;
+    Ok(u32::from_be_bytes(raw_u32))
+}
+
+fn read_raw_bytes(raw_data: &[u8], offset: &mut usize, length: usize) -> Result<Vec<u8>, Error> {
+    if *offset + length > raw_data.len() {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Picture block truncated",
+        ));
+    }
+
+    let raw_bytes = raw_data[*offset..*offset + length].to_vec();
+    *offset += length;
+    Ok(raw_bytes)
+}
+
+fn read_ascii_string(raw_data: &[u8], offset: &mut usize, length: usize) -> Result<String, Error> {
+    let raw_bytes = read_raw_bytes(raw_data, offset, length)?;
+    if !raw_bytes.is_ascii() {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Picture MIME type is not ASCII",
+        ));
+    }
+    Ok(String::from_utf8(raw_bytes).expect("ASCII is valid UTF-8"))
+}
+
+fn read_utf8_string(raw_data: &[u8], offset: &mut usize, length: usize) -> Result<String, Error> {
+    let raw_bytes = read_raw_bytes(raw_data, offset, length)?;
+    String::from_utf8(raw_bytes)
+        .map_err(|e| Error::new(io::ErrorKind::InvalidData, format!("Invalid UTF-8: {e}")))
+}
+
+const SEEKPOINT_SIZE: usize = 18;
+const SEEKPOINT_PLACEHOLDER_SAMPLE: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+
+/// IETF Cellar Flac-14 Section 8.4
+pub struct SeekPoint {
+    pub sample_number: u64,
+    pub stream_offset: u64,
+    pub frame_samples: u16,
+}
+
+impl SeekPoint {
+    fn is_placeholder(&self) -> bool {
+        self.sample_number == SEEKPOINT_PLACEHOLDER_SAMPLE
+    }
+}
+
+pub struct SeekTable {
+    pub seek_points: Vec<SeekPoint>,
+}
+
+impl SeekTable {
+    pub fn new(raw_seektable: &[u8]) -> Result<Self, Error> {
+        if !raw_seektable.len().is_multiple_of(SEEKPOINT_SIZE) {
             return Err(Error::new(
-                io::ErrorKind::InvalidInput,
-                "Not the expected length for a Streaminfo object",
+                io::ErrorKind::InvalidData,
+                "Seektable block is not a multiple of the seek point size",
             ));
         }
 
-        let mut offset = 0;
-        let mut copy_data_increase_offset = |data_slice: &mut [u8], offset_len: usize| {
-            data_slice[..].copy_from_slice(&raw_streaminfo[offset..offset + offset_len]);
-            offset += offset_len;
-        };
-        let mut get_blk_size = || {
-            let mut raw_blk_size = [0u8; 4];
-            copy_data_increase_offset(&mut raw_blk_size[2..], STREAMINFO_BLK_BIT_SIZE);
-            return u32::from_be_bytes(raw_blk_size);
-        };
+        let point_count = raw_seektable.len() / SEEKPOINT_SIZE;
+        let mut seek_points = Vec::with_capacity(point_count);
+        for i in 0..point_count {
+            let raw_point = &raw_seektable[i * SEEKPOINT_SIZE..(i + 1) * SEEKPOINT_SIZE];
 
-        let min_blk_size = get_blk_size();
-        let max_blk_size = get_blk_size();
+            let mut raw_sample_number = [0u8; 8];
+            raw_sample_number.copy_from_slice(&raw_point[0..8]);
+            let sample_number = u64::from_be_bytes(raw_sample_number);
 
-        let mut get_frame_size = || {
-            let mut raw_frame_size = [0u8; 4];
-            copy_data_increase_offset(&mut raw_frame_size[1..], STREAMINFO_FRAME_BIT_SIZE);
-            return u32::from_be_bytes(raw_frame_size);
-        };
+            let mut raw_stream_offset = [0u8; 8];
+            raw_stream_offset.copy_from_slice(&raw_point[8..16]);
+            let stream_offset = u64::from_be_bytes(raw_stream_offset);
 
-        let min_frame_size = get_frame_size();
-        let max_frame_size = get_frame_size();
+            let mut raw_frame_samples = [0u8; 2];
+            raw_frame_samples.copy_from_slice(&raw_point[16..18]);
+            let frame_samples = u16::from_be_bytes(raw_frame_samples);
 
-        let mut raw_sr_nc_bps_tsc = [0u8; 8];
-        copy_data_increase_offset(&mut raw_sr_nc_bps_tsc, 8);
+            seek_points.push(SeekPoint {
+                sample_number,
+                stream_offset,
+                frame_samples,
+            });
+        }
 
-        let raw_sr_nc_bps_tsc = u64::from_be_bytes(raw_sr_nc_bps_tsc);
-        let mut ignore_mask = 0u64;
-        let mut remaining_len = 64;
-        let mut get_sr_nc_bps_tsc_u64 = |num_bits_to_read: u32| {
-            if num_bits_to_read > remaining_len {
-                return 0;
-            }
+        Ok(SeekTable { seek_points })
+    }
 
-            ignore_mask <<= num_bits_to_read;
-            remaining_len -= num_bits_to_read;
-            let ret = (raw_sr_nc_bps_tsc >> remaining_len) ^ ignore_mask;
-            ignore_mask = ignore_mask | ret;
-            ret
-        };
+    /// Returns the highest seek point whose sample number is `<= target_sample`,
+    /// skipping placeholder points, so a caller can jump straight to its byte offset.
+    pub fn find_nearest(&self, target_sample: u64) -> Option<&SeekPoint> {
+        self.seek_points
+            .iter()
+            .filter(|point| !point.is_placeholder() && point.sample_number <= target_sample)
+            .max_by_key(|point| point.sample_number)
+    }
+}
+
+fn read_le_u32(raw_data: &[u8], offset: &mut usize) -> Result<u32, Error> {
+    if *offset + 4 > raw_data.len() {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Vorbis comment block truncated",
+        ));
+    }
+
+    let mut raw_u32 = [0u8; 4];
+    raw_u32.copy_from_slice(&raw_data[*offset..*offset + 4]);
+    *offset += 4;
+    Ok(u32::from_le_bytes(raw_u32))
+}
+
+fn read_le_string(raw_data: &[u8], offset: &mut usize, length: usize) -> Result<String, Error> {
+    if *offset + length > raw_data.len() {
+        return Err(Error::new(
+            io::ErrorKind::InvalidData,
+            "Vorbis comment block truncated",
+        ));
+    }
+
+    let raw_string = &raw_data[*offset..*offset + length];
+    *offset += length;
+    String::from_utf8(raw_string.to_vec())
+        .map_err(|e| Error::new(io::ErrorKind::InvalidData, format!("Invalid UTF-8: {e}")))
+}
 
-        let sample_rate = u32::try_from(get_sr_nc_bps_tsc_u64(20)).expect("Value not a u32");
-        let num_channels = u32::try_from(get_sr_nc_bps_tsc_u64(3)).expect("Value not a u8");
-        let bits_per_sample = u32::try_from(get_sr_nc_bps_tsc_u64(5)).expect("Value not a u8");
-        let total_sample_count = get_sr_nc_bps_tsc_u64(36);
+/// IETF Cellar Flac-14 Section 8.6, carried over from the Vorbis comment header.
+/// Unlike the rest of FLAC, this block is little-endian.
+pub struct VorbisComment {
+    pub vendor_string: String,
+    pub comments: Vec<(String, String)>,
+}
+
+impl VorbisComment {
+    pub fn new(raw_vorbis_comment: &[u8]) -> Result<Self, Error> {
+        let mut offset = 0usize;
+
+        let vendor_length = read_le_u32(raw_vorbis_comment, &mut offset)? as usize;
+        let vendor_string = read_le_string(raw_vorbis_comment, &mut offset, vendor_length)?;
+
+        let comment_count = read_le_u32(raw_vorbis_comment, &mut offset)?;
+        let mut comments = Vec::with_capacity(comment_count as usize);
+        for _ in 0..comment_count {
+            let comment_length = read_le_u32(raw_vorbis_comment, &mut offset)? as usize;
+            let raw_comment = read_le_string(raw_vorbis_comment, &mut offset, comment_length)?;
+            let (field, value) = raw_comment.split_once('=').ok_or_else(|| {
+                Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Comment missing '=' separator: {raw_comment}"),
+                )
+            })?;
+            comments.push((field.to_string(), value.to_string()));
+        }
 
-        let mut raw_md5_checksum = [0u8; 16];
-        copy_data_increase_offset(&mut raw_md5_checksum, 16);
-        let md5_checksum = u128::from_be_bytes(raw_md5_checksum);
+        Ok(VorbisComment {
+            vendor_string,
+            comments,
+        })
+    }
+
+    /// Looks up a comment field, ignoring ASCII case as FLAC field names require.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.comments
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.as_str())
+    }
+}
 
-        return Ok(Streaminfo {
+impl Streaminfo {
+    pub fn new(raw_streaminfo: &[u8]) -> Result<Self, Error> {
+        if raw_streaminfo.len() < STREAMINFO_SIZE {
+            return Err(Error::new(
+                io::ErrorKind::InvalidInput,
+                "Not the expected length for a Streaminfo object",
+            ));
+        }
+
+        let mut reader = BitReader::new(Cursor::new(raw_streaminfo));
+
+        let min_blk_size = reader.read_bits(STREAMINFO_BLK_BIT_SIZE as u32)? as u32;
+        let max_blk_size = reader.read_bits(STREAMINFO_BLK_BIT_SIZE as u32)? as u32;
+        let min_frame_size = reader.read_bits(STREAMINFO_FRAME_BIT_SIZE as u32)? as u32;
+        let max_frame_size = reader.read_bits(STREAMINFO_FRAME_BIT_SIZE as u32)? as u32;
+        let sample_rate = reader.read_bits(STREAMINFO_SAMPLE_RATE_BIT_SIZE as u32)? as u32;
+        let num_channels = reader.read_bits(STREAMINFO_NUM_CHANNELS_BIT_SIZE as u32)? as u32 + 1;
+        let bits_per_sample = reader.read_bits(STREAMINFO_BITS_PER_SAMPLE_BIT_SIZE as u32)? as u32 + 1;
+        let total_sample_count = reader.read_bits(STREAMINFO_TOTAL_SAMPLE_COUNT_BIT_SIZE as u32)?;
+        let md5_checksum =
+            (u128::from(reader.read_bits(64)?) << 64) | u128::from(reader.read_bits(64)?);
+
+        Ok(Streaminfo {
             min_blk_size,
             max_blk_size,
             min_frame_size,
@@ -167,6 +445,6 @@ impl Streaminfo {
             bits_per_sample,
             total_sample_count,
             md5_checksum,
-        });
+        })
     }
 }
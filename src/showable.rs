@@ -0,0 +1,7 @@
+/**
+ * Copyright (c) Ben Serrano. All rights reserved.
+ * Licensed under the MIT License. See LICENSE in the project root for license information
+ */
+pub trait Showable {
+    fn show_details(&self);
+}